@@ -0,0 +1,339 @@
+// src-tauri/src/activation.rs
+//
+// Cryptolens-style online activation: a license is bound to a specific machine via a
+// signed activation record issued by an activation server, cached under the plugin's
+// directory, and re-verified (signature + fingerprint + expiry) on every launch. Air-gapped
+// datacenters can instead ingest the same record out-of-band with `import_offline_activation`.
+
+use std::path::{Path, PathBuf};
+
+use base64::{engine::general_purpose, Engine as _};
+use ring::digest::{digest, SHA256};
+use ring::signature::{UnparsedPublicKey, RSA_PKCS1_2048_8192_SHA256};
+use rsa::pkcs1::EncodeRsaPublicKey;
+use rsa::pkcs8::DecodePublicKey;
+use rsa::RsaPublicKey;
+use serde::{Deserialize, Serialize};
+use tauri::command;
+
+#[derive(Serialize, Deserialize, Clone)]
+struct ActivationResponse {
+  #[serde(rename = "pluginId")]
+  plugin_id: String,
+  #[serde(rename = "licenseKey")]
+  license_key: String,
+  #[serde(rename = "machineFingerprint")]
+  machine_fingerprint: String,
+  #[serde(rename = "activatedAt")]
+  activated_at: i64,
+  expires: Option<i64>,
+  #[serde(rename = "maxMachines")]
+  max_machines: u32,
+  #[serde(rename = "leaseTtlSeconds")]
+  lease_ttl_seconds: i64,
+  signature: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ActivationStatus {
+  pub active: bool,
+  pub reason: Option<String>,
+  /// The seat cap the server attached to this activation, surfaced for the UI. The
+  /// client has no visibility into how many other machines are currently activated,
+  /// so the server — not this check — is what actually enforces the cap.
+  pub max_machines: Option<u32>,
+}
+
+fn activation_path(plugin_dir: &str) -> PathBuf {
+  Path::new(plugin_dir).join(".activation.json")
+}
+
+/// Hash of hostname + primary MAC address + OS, stable across restarts of the same
+/// machine but not portable to another one.
+fn machine_fingerprint() -> Result<String, String> {
+  let hostname = hostname::get()
+    .map_err(|e| format!("Failed to read hostname: {}", e))?
+    .to_string_lossy()
+    .to_string();
+  let mac = mac_address::get_mac_address()
+    .map_err(|e| format!("Failed to read MAC address: {}", e))?
+    .map(|addr| addr.to_string())
+    .unwrap_or_default();
+
+  let material = format!("{}:{}:{}", hostname, mac, std::env::consts::OS);
+  let hash = digest(&SHA256, material.as_bytes());
+  Ok(general_purpose::STANDARD.encode(hash.as_ref()))
+}
+
+/// Verifies the RSA signature over the same bytes the server signed: the ActivationResponse
+/// serialized with its `signature` field set to an empty string. The public key is
+/// re-encoded as PKCS#1 DER for ring.
+fn verify_activation(activation: &ActivationResponse, public_key_pem: &str) -> Result<bool, String> {
+  let mut unsigned = activation.clone();
+  unsigned.signature = String::new();
+  let payload = serde_json::to_vec(&unsigned).map_err(|e| e.to_string())?;
+
+  let signature_bytes = general_purpose::STANDARD
+    .decode(&activation.signature)
+    .map_err(|e| format!("Invalid signature encoding: {}", e))?;
+  let rsa_pub = RsaPublicKey::from_public_key_pem(public_key_pem)
+    .map_err(|e| format!("Invalid public key: {}", e))?;
+  let pkcs1_der = rsa_pub
+    .to_pkcs1_der()
+    .map_err(|e| format!("Failed to re-encode public key: {}", e))?;
+
+  Ok(
+    UnparsedPublicKey::new(&RSA_PKCS1_2048_8192_SHA256, pkcs1_der.as_bytes())
+      .verify(&payload, &signature_bytes)
+      .is_ok(),
+  )
+}
+
+fn check_cached_activation(
+  activation: &ActivationResponse,
+  plugin_id: &str,
+  public_key_pem: &str,
+) -> Result<ActivationStatus, String> {
+  if activation.plugin_id != plugin_id {
+    return Ok(ActivationStatus {
+      active: false,
+      reason: Some("activation is for a different plugin".to_string()),
+      max_machines: Some(activation.max_machines),
+    });
+  }
+  if !verify_activation(activation, public_key_pem)? {
+    return Ok(ActivationStatus { active: false, reason: Some("bad signature".to_string()), max_machines: None });
+  }
+  if activation.machine_fingerprint != machine_fingerprint()? {
+    return Ok(ActivationStatus {
+      active: false,
+      reason: Some("bound to a different machine".to_string()),
+      max_machines: Some(activation.max_machines),
+    });
+  }
+
+  let now = chrono::Utc::now().timestamp();
+  if let Some(expires) = activation.expires {
+    if now > expires {
+      return Ok(ActivationStatus {
+        active: false,
+        reason: Some("expired".to_string()),
+        max_machines: Some(activation.max_machines),
+      });
+    }
+  }
+  // The floating-license lease must be renewed by re-activating before it elapses;
+  // a node that crashed and never renewed naturally frees its seat once this lapses.
+  if now > activation.activated_at + activation.lease_ttl_seconds {
+    return Ok(ActivationStatus {
+      active: false,
+      reason: Some("lease expired".to_string()),
+      max_machines: Some(activation.max_machines),
+    });
+  }
+
+  Ok(ActivationStatus { active: true, reason: None, max_machines: Some(activation.max_machines) })
+}
+
+/// Computes this machine's fingerprint, activates `license_key` against `server_url`,
+/// and caches the server's signed activation response under `plugin_dir`.
+#[command]
+pub async fn activate_license(
+  plugin_id: String,
+  license_key: String,
+  server_url: String,
+  plugin_dir: String,
+  public_key: String,
+) -> Result<ActivationStatus, String> {
+  let fingerprint = machine_fingerprint()?;
+
+  let client = reqwest::Client::new();
+  let activation: ActivationResponse = client
+    .post(format!("{}/activate", server_url))
+    .json(&serde_json::json!({
+      "pluginId": plugin_id,
+      "licenseKey": license_key,
+      "machineFingerprint": fingerprint,
+    }))
+    .send()
+    .await
+    .map_err(|e| e.to_string())?
+    .json()
+    .await
+    .map_err(|e| format!("Invalid activation response: {}", e))?;
+
+  // Don't trust the server's response just because it parsed: verify its signature
+  // and that it's bound to the plugin/machine we actually requested, with the same
+  // checks check_activation runs on the cached copy, before caching it as active.
+  let status = check_cached_activation(&activation, &plugin_id, &public_key)?;
+  if !status.active {
+    return Ok(status);
+  }
+
+  let contents = serde_json::to_string(&activation).map_err(|e| e.to_string())?;
+  std::fs::create_dir_all(&plugin_dir).map_err(|e| e.to_string())?;
+  std::fs::write(activation_path(&plugin_dir), contents).map_err(|e| e.to_string())?;
+
+  Ok(status)
+}
+
+/// Re-verifies the cached activation blob's signature, machine binding, and lease/expiry
+/// without making a network call.
+#[command]
+pub async fn check_activation(plugin_id: String, plugin_dir: String, public_key: String) -> Result<ActivationStatus, String> {
+  let path = activation_path(&plugin_dir);
+  if !path.exists() {
+    return Ok(ActivationStatus { active: false, reason: Some("not activated".to_string()), max_machines: None });
+  }
+  let contents = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+  let activation: ActivationResponse =
+    serde_json::from_str(&contents).map_err(|e| format!("Corrupt activation cache: {}", e))?;
+
+  check_cached_activation(&activation, &plugin_id, &public_key)
+}
+
+/// Ingests a signed activation file produced out-of-band by the activation server, so
+/// air-gapped datacenter nodes can activate without ever reaching the network.
+#[command]
+pub async fn import_offline_activation(path: String, plugin_dir: String, public_key: String) -> Result<ActivationStatus, String> {
+  let contents = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+  let activation: ActivationResponse =
+    serde_json::from_str(&contents).map_err(|e| format!("Invalid offline activation file: {}", e))?;
+
+  if !verify_activation(&activation, &public_key)? {
+    return Ok(ActivationStatus { active: false, reason: Some("bad signature".to_string()), max_machines: None });
+  }
+  if activation.machine_fingerprint != machine_fingerprint()? {
+    return Ok(ActivationStatus {
+      active: false,
+      reason: Some("bound to a different machine".to_string()),
+      max_machines: Some(activation.max_machines),
+    });
+  }
+
+  std::fs::create_dir_all(&plugin_dir).map_err(|e| e.to_string())?;
+  std::fs::write(activation_path(&plugin_dir), &contents).map_err(|e| e.to_string())?;
+
+  Ok(ActivationStatus { active: true, reason: None, max_machines: Some(activation.max_machines) })
+}
+
+/// Frees this machine's seat. Best-effort notifies `server_url` so the seat becomes
+/// immediately available to other machines, then always clears the local cache.
+#[command]
+pub async fn release_activation(plugin_id: String, server_url: Option<String>, plugin_dir: String) -> Result<(), String> {
+  if let (Some(server_url), Ok(fingerprint)) = (server_url, machine_fingerprint()) {
+    let client = reqwest::Client::new();
+    let _ = client
+      .post(format!("{}/release", server_url))
+      .json(&serde_json::json!({
+        "pluginId": plugin_id,
+        "machineFingerprint": fingerprint,
+      }))
+      .send()
+      .await;
+  }
+
+  let path = activation_path(&plugin_dir);
+  if path.exists() {
+    std::fs::remove_file(path).map_err(|e| e.to_string())?;
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ring::rand::SystemRandom;
+  use ring::signature::{RsaKeyPair, RSA_PKCS1_SHA256};
+  use rsa::pkcs8::{DecodePrivateKey, EncodePrivateKey, LineEnding};
+
+  fn generate_rsa_pem_pair() -> (String, String) {
+    let mut rng = rand::thread_rng();
+    let private_key = rsa::RsaPrivateKey::new(&mut rng, 2048).unwrap();
+    let public_key = RsaPublicKey::from(&private_key);
+    (
+      private_key.to_pkcs8_pem(LineEnding::LF).unwrap().to_string(),
+      public_key.to_public_key_pem(LineEnding::LF).unwrap(),
+    )
+  }
+
+  fn sign_activation(activation: &mut ActivationResponse, private_key_pem: &str) {
+    activation.signature = String::new();
+    let payload = serde_json::to_vec(&activation).unwrap();
+
+    let rsa_key = rsa::RsaPrivateKey::from_pkcs8_pem(private_key_pem).unwrap();
+    let pkcs8_der = rsa_key.to_pkcs8_der().unwrap();
+    let key_pair = RsaKeyPair::from_pkcs8(pkcs8_der.as_bytes()).unwrap();
+
+    let rng = SystemRandom::new();
+    let mut signature = vec![0u8; key_pair.public_modulus_len()];
+    key_pair.sign(&RSA_PKCS1_SHA256, &rng, &payload, &mut signature).unwrap();
+    activation.signature = general_purpose::STANDARD.encode(&signature);
+  }
+
+  fn sample_activation() -> ActivationResponse {
+    ActivationResponse {
+      plugin_id: "plugin-a".to_string(),
+      license_key: "LYC-TEST".to_string(),
+      machine_fingerprint: machine_fingerprint().unwrap(),
+      activated_at: chrono::Utc::now().timestamp(),
+      expires: None,
+      max_machines: 5,
+      lease_ttl_seconds: 3600,
+      signature: String::new(),
+    }
+  }
+
+  #[test]
+  fn accepts_valid_signed_activation() {
+    let (private_key_pem, public_key_pem) = generate_rsa_pem_pair();
+    let mut activation = sample_activation();
+    sign_activation(&mut activation, &private_key_pem);
+
+    let status = check_cached_activation(&activation, "plugin-a", &public_key_pem).unwrap();
+
+    assert!(status.active);
+    assert_eq!(status.max_machines, Some(5));
+  }
+
+  #[test]
+  fn rejects_tampered_signature() {
+    let (private_key_pem, public_key_pem) = generate_rsa_pem_pair();
+    let mut activation = sample_activation();
+    sign_activation(&mut activation, &private_key_pem);
+
+    let mut sig_bytes = general_purpose::STANDARD.decode(&activation.signature).unwrap();
+    sig_bytes[0] ^= 0xFF;
+    activation.signature = general_purpose::STANDARD.encode(sig_bytes);
+
+    let status = check_cached_activation(&activation, "plugin-a", &public_key_pem).unwrap();
+
+    assert!(!status.active);
+    assert_eq!(status.reason, Some("bad signature".to_string()));
+  }
+
+  #[test]
+  fn rejects_activation_for_a_different_plugin() {
+    let (private_key_pem, public_key_pem) = generate_rsa_pem_pair();
+    let mut activation = sample_activation();
+    sign_activation(&mut activation, &private_key_pem);
+
+    let status = check_cached_activation(&activation, "plugin-b", &public_key_pem).unwrap();
+
+    assert!(!status.active);
+    assert_eq!(status.reason, Some("activation is for a different plugin".to_string()));
+  }
+
+  #[test]
+  fn rejects_lapsed_lease() {
+    let (private_key_pem, public_key_pem) = generate_rsa_pem_pair();
+    let mut activation = sample_activation();
+    activation.activated_at = chrono::Utc::now().timestamp() - activation.lease_ttl_seconds - 10;
+    sign_activation(&mut activation, &private_key_pem);
+
+    let status = check_cached_activation(&activation, "plugin-a", &public_key_pem).unwrap();
+
+    assert!(!status.active);
+    assert_eq!(status.reason, Some("lease expired".to_string()));
+  }
+}
@@ -0,0 +1,206 @@
+// src-tauri/src/permissions.rs
+//
+// Generates Tauri v2 ACL permission and capability manifests for scaffolded plugins, so
+// least-privilege command exposure can be managed from the studio instead of hand-edited.
+// Permissions (TOML, one file per identifier) live under `<plugin_dir>/permissions/`;
+// capabilities (JSON, one file per identifier) live under `<plugin_dir>/capabilities/`,
+// matching the layout Tauri itself expects.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::command;
+
+#[derive(Serialize, Deserialize, Clone)]
+struct PermissionCommands {
+  #[serde(default)]
+  allow: Vec<String>,
+  #[serde(default)]
+  deny: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PermissionManifest {
+  identifier: String,
+  commands: PermissionCommands,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  scope: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CapabilityManifest {
+  identifier: String,
+  windows: Vec<String>,
+  permissions: Vec<String>,
+}
+
+/// Permission/capability identifiers are `namespace:kebab-name`; turn that into a file
+/// stem that is the same on Windows and Unix by replacing `:` with `-`. Empty identifiers
+/// and ones containing `..`, `/` or `\` are rejected; no other characters are checked.
+/// The mapping is not reversible, so identifiers are read back from manifest contents.
+fn identifier_to_file_stem(identifier: &str) -> Result<String, String> {
+  if identifier.is_empty() || identifier.contains("..") || identifier.contains('/') || identifier.contains('\\') {
+    return Err(format!("Invalid permission identifier: {}", identifier));
+  }
+  Ok(identifier.replace(':', "-"))
+}
+
+fn permissions_dir(plugin_dir: &str) -> PathBuf {
+  Path::new(plugin_dir).join("permissions")
+}
+
+fn capabilities_dir(plugin_dir: &str) -> PathBuf {
+  Path::new(plugin_dir).join("capabilities")
+}
+
+fn permission_path(plugin_dir: &str, identifier: &str) -> Result<PathBuf, String> {
+  Ok(permissions_dir(plugin_dir).join(format!("{}.toml", identifier_to_file_stem(identifier)?)))
+}
+
+fn capability_path(plugin_dir: &str, identifier: &str) -> Result<PathBuf, String> {
+  Ok(capabilities_dir(plugin_dir).join(format!("{}.json", identifier_to_file_stem(identifier)?)))
+}
+
+/// Writes a permission manifest granting `commands` (and, optionally, a filesystem-style
+/// `scope`) under `identifier`.
+#[command]
+pub fn create_permission(
+  plugin_dir: String,
+  identifier: String,
+  commands: Vec<String>,
+  scope: Option<Value>,
+) -> Result<(), String> {
+  let manifest = PermissionManifest {
+    identifier: identifier.clone(),
+    commands: PermissionCommands { allow: commands, deny: vec![] },
+    scope,
+  };
+
+  let dir = permissions_dir(&plugin_dir);
+  std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+  let toml = toml::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+  std::fs::write(permission_path(&plugin_dir, &identifier)?, toml).map_err(|e| e.to_string())
+}
+
+/// Lists every permission manifest currently defined for the plugin.
+#[command]
+pub fn list_permissions(plugin_dir: String) -> Result<Vec<PermissionManifest>, String> {
+  let dir = permissions_dir(&plugin_dir);
+  if !dir.exists() {
+    return Ok(vec![]);
+  }
+
+  let mut manifests = vec![];
+  for entry in std::fs::read_dir(&dir).map_err(|e| e.to_string())? {
+    let entry = entry.map_err(|e| e.to_string())?;
+    let path = entry.path();
+    if path.extension().and_then(|ext| ext.to_str()) != Some("toml") {
+      continue;
+    }
+    let contents = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let manifest: PermissionManifest = toml::from_str(&contents)
+      .map_err(|e| format!("Invalid permission manifest {}: {}", path.display(), e))?;
+    manifests.push(manifest);
+  }
+  Ok(manifests)
+}
+
+/// Deletes the permission manifest for `identifier`, if present.
+#[command]
+pub fn remove_permission(plugin_dir: String, identifier: String) -> Result<(), String> {
+  let path = permission_path(&plugin_dir, &identifier)?;
+  if path.exists() {
+    std::fs::remove_file(path).map_err(|e| e.to_string())?;
+  }
+  Ok(())
+}
+
+/// Writes a capability manifest that grants `permissions` to `windows`.
+#[command]
+pub fn create_capability(
+  plugin_dir: String,
+  identifier: String,
+  permissions: Vec<String>,
+  windows: Vec<String>,
+) -> Result<(), String> {
+  let manifest = CapabilityManifest { identifier: identifier.clone(), windows, permissions };
+
+  let dir = capabilities_dir(&plugin_dir);
+  std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+  let json = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+  std::fs::write(capability_path(&plugin_dir, &identifier)?, json).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn temp_plugin_dir(label: &str) -> String {
+    let dir = std::env::temp_dir().join(format!(
+      "dps-test-permissions-{}-{}-{}",
+      label,
+      std::process::id(),
+      chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.to_string_lossy().to_string()
+  }
+
+  #[test]
+  fn create_and_list_permission_round_trips() {
+    let plugin_dir = temp_plugin_dir("roundtrip");
+
+    create_permission(
+      plugin_dir.clone(),
+      "fs:allow-read".to_string(),
+      vec!["read_file".to_string()],
+      Some(serde_json::json!({ "path": "$APPDATA/*" })),
+    )
+    .unwrap();
+
+    let manifests = list_permissions(plugin_dir).unwrap();
+
+    assert_eq!(manifests.len(), 1);
+    assert_eq!(manifests[0].identifier, "fs:allow-read");
+    assert_eq!(manifests[0].commands.allow, vec!["read_file".to_string()]);
+    assert!(manifests[0].commands.deny.is_empty());
+  }
+
+  #[test]
+  fn remove_permission_deletes_manifest() {
+    let plugin_dir = temp_plugin_dir("remove");
+    create_permission(plugin_dir.clone(), "fs:allow-read".to_string(), vec!["read_file".to_string()], None).unwrap();
+
+    remove_permission(plugin_dir.clone(), "fs:allow-read".to_string()).unwrap();
+
+    assert!(list_permissions(plugin_dir).unwrap().is_empty());
+  }
+
+  #[test]
+  fn create_permission_rejects_path_traversal_identifiers() {
+    let plugin_dir = temp_plugin_dir("traversal");
+    let err = create_permission(plugin_dir, "../../etc/passwd".to_string(), vec![], None).unwrap_err();
+    assert!(err.contains("Invalid permission identifier"));
+  }
+
+  #[test]
+  fn create_capability_writes_readable_manifest() {
+    let plugin_dir = temp_plugin_dir("capability");
+
+    create_capability(
+      plugin_dir.clone(),
+      "main-capability".to_string(),
+      vec!["fs:allow-read".to_string()],
+      vec!["main".to_string()],
+    )
+    .unwrap();
+
+    let contents = std::fs::read_to_string(capability_path(&plugin_dir, "main-capability").unwrap()).unwrap();
+    let manifest: CapabilityManifest = serde_json::from_str(&contents).unwrap();
+
+    assert_eq!(manifest.identifier, "main-capability");
+    assert_eq!(manifest.windows, vec!["main".to_string()]);
+    assert_eq!(manifest.permissions, vec!["fs:allow-read".to_string()]);
+  }
+}
@@ -0,0 +1,394 @@
+// src-tauri/src/trust_root.rs
+//
+// A TUF-inspired trust root for distributing and rotating the public keys used to
+// verify licenses. `root.json` lists the authorized license-signing keys and an
+// N-of-M signature threshold; `keys.json` maps each plugin to the key id(s) that
+// may sign its licenses. Both are cached locally and only replaced by a fetched
+// update once its signatures, version (no rollback), and expiry all check out.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use base64::{engine::general_purpose, Engine as _};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::command;
+
+#[derive(Serialize, Deserialize, Clone)]
+struct MetaSignature {
+  keyid: String,
+  sig: String,
+}
+
+/// An envelope as it arrives on the wire: `signed` is kept as a `Value` (whose map type
+/// defaults to a `BTreeMap`, i.e. sorted-key JSON) so it can be re-serialized into the
+/// exact canonical bytes the issuer signed, rather than round-tripped through a struct
+/// whose derived field order — or a `HashMap`'s randomized iteration order — would not
+/// reproduce them.
+#[derive(Deserialize, Clone)]
+struct Envelope {
+  signed: Value,
+  signatures: Vec<MetaSignature>,
+}
+
+/// A parsed, verifiable piece of trust-root metadata: the strongly-typed `signed` body
+/// for field access, the canonical bytes that were actually signed, and the signatures
+/// over those bytes.
+struct CachedMetadata<T> {
+  signed: T,
+  canonical_signed: Vec<u8>,
+  signatures: Vec<MetaSignature>,
+}
+
+fn parse_envelope<T: for<'de> Deserialize<'de>>(body: &str) -> Result<CachedMetadata<T>, String> {
+  let envelope: Envelope = serde_json::from_str(body).map_err(|e| e.to_string())?;
+  let canonical_signed = serde_json::to_vec(&envelope.signed).map_err(|e| e.to_string())?;
+  let signed = serde_json::from_value(envelope.signed).map_err(|e| e.to_string())?;
+  Ok(CachedMetadata { signed, canonical_signed, signatures: envelope.signatures })
+}
+
+/// A trusted key entry, tagged with the encoding of `value` so callers don't have to
+/// assume Ed25519: this root's own self-signing keys are always `"ed25519"` (raw, used
+/// by `verify_threshold`), but keys distributed here for plugin license verification may
+/// also be `"rsa-spki-der"` (PKCS#8 SubjectPublicKeyInfo DER) or `"ecdsa-p256"` (raw
+/// P-256 point), depending on what algorithm the plugin's licenses are signed with.
+#[derive(Serialize, Deserialize, Clone)]
+struct TrustedKeyEntry {
+  kty: String,
+  /// base64-encoded key material, encoded per `kty`.
+  value: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct RootMetadata {
+  #[serde(rename = "type")]
+  kind: String,
+  version: u64,
+  expires: String,
+  threshold: u32,
+  /// key id -> kty-tagged trusted key entry (see `TrustedKeyEntry`)
+  keys: HashMap<String, TrustedKeyEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct KeysMetadata {
+  #[serde(rename = "type")]
+  kind: String,
+  version: u64,
+  expires: String,
+  /// plugin id -> key ids authorized to sign that plugin's licenses
+  targets: HashMap<String, Vec<String>>,
+}
+
+fn root_path(cache_dir: &str) -> PathBuf {
+  Path::new(cache_dir).join("root.json")
+}
+
+fn keys_path(cache_dir: &str) -> PathBuf {
+  Path::new(cache_dir).join("keys.json")
+}
+
+fn is_expired(expires: &str) -> Result<bool, String> {
+  let expires = chrono::DateTime::parse_from_rfc3339(expires)
+    .map_err(|e| format!("Invalid expires timestamp: {}", e))?;
+  Ok(chrono::Utc::now() > expires)
+}
+
+fn verify_threshold(
+  canonical_signed: &[u8],
+  signatures: &[MetaSignature],
+  trusted_keys: &HashMap<String, TrustedKeyEntry>,
+  threshold: u32,
+) -> Result<bool, String> {
+  // Count distinct signing keys, so one key listed twice cannot meet an N-of-M threshold.
+  let mut signers = HashSet::new();
+  for signature in signatures {
+    let Some(entry) = trusted_keys.get(&signature.keyid) else {
+      continue;
+    };
+    // root.json/keys.json are always self-signed with Ed25519 regardless of what
+    // kind of keys they go on to distribute for plugin license verification.
+    if entry.kty != "ed25519" {
+      continue;
+    }
+    let pubkey = match general_purpose::STANDARD.decode(&entry.value) {
+      Ok(bytes) => bytes,
+      Err(_) => continue,
+    };
+    let sig_bytes = match general_purpose::STANDARD.decode(&signature.sig) {
+      Ok(bytes) => bytes,
+      Err(_) => continue,
+    };
+    if UnparsedPublicKey::new(&ED25519, &pubkey)
+      .verify(canonical_signed, &sig_bytes)
+      .is_ok()
+    {
+      signers.insert(signature.keyid.as_str());
+    }
+  }
+  Ok(signers.len() >= threshold as usize)
+}
+
+fn read_cached<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<Option<CachedMetadata<T>>, String> {
+  if !path.exists() {
+    return Ok(None);
+  }
+  let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+  parse_envelope(&contents)
+    .map(Some)
+    .map_err(|e| format!("Corrupt cached metadata at {}: {}", path.display(), e))
+}
+
+fn write_atomically(path: &Path, contents: &str) -> Result<(), String> {
+  let tmp_path = path.with_extension("json.tmp");
+  std::fs::write(&tmp_path, contents).map_err(|e| e.to_string())?;
+  std::fs::rename(&tmp_path, path).map_err(|e| e.to_string())
+}
+
+/// Fetches `root.json` and `keys.json` from `base_url`, verifies each against the keys
+/// currently trusted by the local cache, rejects rollback (lower `version`) and expired
+/// metadata, and only once both files pass replaces the cached `root.json` and then
+/// `keys.json`, each by an atomic rename; the two files are not swapped as one unit.
+#[command]
+pub async fn refresh_trust_root(base_url: String, cache_dir: String) -> Result<(), String> {
+  std::fs::create_dir_all(&cache_dir).map_err(|e| e.to_string())?;
+
+  let current_root = read_cached::<RootMetadata>(&root_path(&cache_dir))?
+    .ok_or_else(|| "No locally trusted root.json; one must be seeded with the app before it can be refreshed".to_string())?;
+
+  let fetched_root_body = reqwest::get(format!("{}/root.json", base_url))
+    .await
+    .map_err(|e| e.to_string())?
+    .text()
+    .await
+    .map_err(|e| e.to_string())?;
+  let fetched_root: CachedMetadata<RootMetadata> =
+    parse_envelope(&fetched_root_body).map_err(|e| format!("Invalid root.json: {}", e))?;
+
+  if fetched_root.signed.version < current_root.signed.version {
+    return Err(format!(
+      "Rejected root.json rollback: fetched version {} is older than trusted version {}",
+      fetched_root.signed.version, current_root.signed.version
+    ));
+  }
+  if is_expired(&fetched_root.signed.expires)? {
+    return Err("Fetched root.json has expired".to_string());
+  }
+  if !verify_threshold(
+    &fetched_root.canonical_signed,
+    &fetched_root.signatures,
+    &current_root.signed.keys,
+    current_root.signed.threshold,
+  )? {
+    return Err("Fetched root.json did not meet the signature threshold".to_string());
+  }
+
+  let current_keys = read_cached::<KeysMetadata>(&keys_path(&cache_dir))?;
+
+  let fetched_keys_body = reqwest::get(format!("{}/keys.json", base_url))
+    .await
+    .map_err(|e| e.to_string())?
+    .text()
+    .await
+    .map_err(|e| e.to_string())?;
+  let fetched_keys: CachedMetadata<KeysMetadata> =
+    parse_envelope(&fetched_keys_body).map_err(|e| format!("Invalid keys.json: {}", e))?;
+
+  if let Some(current_keys) = &current_keys {
+    if fetched_keys.signed.version < current_keys.signed.version {
+      return Err(format!(
+        "Rejected keys.json rollback: fetched version {} is older than trusted version {}",
+        fetched_keys.signed.version, current_keys.signed.version
+      ));
+    }
+  }
+  if is_expired(&fetched_keys.signed.expires)? {
+    return Err("Fetched keys.json has expired".to_string());
+  }
+  // keys.json is signed by the (now-verified) root keys, not by itself.
+  if !verify_threshold(
+    &fetched_keys.canonical_signed,
+    &fetched_keys.signatures,
+    &fetched_root.signed.keys,
+    fetched_root.signed.threshold,
+  )? {
+    return Err("Fetched keys.json did not meet the signature threshold".to_string());
+  }
+
+  write_atomically(&root_path(&cache_dir), &fetched_root_body)?;
+  write_atomically(&keys_path(&cache_dir), &fetched_keys_body)?;
+  Ok(())
+}
+
+/// Resolves the trusted public key (base64-encoded) that is authorized to sign
+/// `plugin_id`'s licenses under `key_id`, using the locally cached trust root.
+///
+/// Rejects a cache whose `root.json`/`keys.json` has passed its `expires` timestamp: a
+/// client that stops calling `refresh_trust_root` must not go on trusting stale metadata
+/// forever, or a revoked/rotated key would stay trusted indefinitely (a freeze attack).
+pub fn resolve_trusted_key(cache_dir: &str, plugin_id: &str, key_id: &str) -> Result<String, String> {
+  let root = read_cached::<RootMetadata>(&root_path(cache_dir))?
+    .ok_or_else(|| "No locally trusted root.json".to_string())?;
+  let keys = read_cached::<KeysMetadata>(&keys_path(cache_dir))?
+    .ok_or_else(|| "No locally trusted keys.json".to_string())?;
+
+  if is_expired(&root.signed.expires)? {
+    return Err("Locally trusted root.json has expired; call refresh_trust_root".to_string());
+  }
+  if is_expired(&keys.signed.expires)? {
+    return Err("Locally trusted keys.json has expired; call refresh_trust_root".to_string());
+  }
+
+  let authorized = keys
+    .signed
+    .targets
+    .get(plugin_id)
+    .ok_or_else(|| format!("No trusted keys registered for plugin '{}'", plugin_id))?;
+  if !authorized.iter().any(|id| id == key_id) {
+    return Err(format!("Key '{}' is not authorized for plugin '{}'", key_id, plugin_id));
+  }
+
+  root
+    .signed
+    .keys
+    .get(key_id)
+    .map(|entry| entry.value.clone())
+    .ok_or_else(|| format!("Unknown key id '{}' in trust root", key_id))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ring::rand::SystemRandom;
+  use ring::signature::{Ed25519KeyPair, KeyPair};
+
+  fn generate_ed25519() -> (Ed25519KeyPair, String) {
+    let rng = SystemRandom::new();
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+    let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+    let public_key_b64 = general_purpose::STANDARD.encode(key_pair.public_key().as_ref());
+    (key_pair, public_key_b64)
+  }
+
+  fn sign(key_pair: &Ed25519KeyPair, keyid: &str, canonical_signed: &[u8]) -> MetaSignature {
+    let sig = key_pair.sign(canonical_signed);
+    MetaSignature { keyid: keyid.to_string(), sig: general_purpose::STANDARD.encode(sig.as_ref()) }
+  }
+
+  #[test]
+  fn verify_threshold_accepts_sufficient_signatures() {
+    let (key_a, pub_a) = generate_ed25519();
+    let (key_b, pub_b) = generate_ed25519();
+    let canonical_signed = b"canonical root bytes".to_vec();
+    let signatures = vec![
+      sign(&key_a, "key-a", &canonical_signed),
+      sign(&key_b, "key-b", &canonical_signed),
+    ];
+    let trusted_keys = HashMap::from([
+      ("key-a".to_string(), TrustedKeyEntry { kty: "ed25519".to_string(), value: pub_a }),
+      ("key-b".to_string(), TrustedKeyEntry { kty: "ed25519".to_string(), value: pub_b }),
+    ]);
+
+    assert!(verify_threshold(&canonical_signed, &signatures, &trusted_keys, 2).unwrap());
+  }
+
+  #[test]
+  fn verify_threshold_rejects_below_threshold() {
+    let (key_a, pub_a) = generate_ed25519();
+    let (_key_b, pub_b) = generate_ed25519();
+    let canonical_signed = b"canonical root bytes".to_vec();
+    // Only one of the two required signers actually signed.
+    let signatures = vec![sign(&key_a, "key-a", &canonical_signed)];
+    let trusted_keys = HashMap::from([
+      ("key-a".to_string(), TrustedKeyEntry { kty: "ed25519".to_string(), value: pub_a }),
+      ("key-b".to_string(), TrustedKeyEntry { kty: "ed25519".to_string(), value: pub_b }),
+    ]);
+
+    assert!(!verify_threshold(&canonical_signed, &signatures, &trusted_keys, 2).unwrap());
+  }
+
+  #[test]
+  fn verify_threshold_rejects_tampered_bytes() {
+    let (key_a, pub_a) = generate_ed25519();
+    let canonical_signed = b"canonical root bytes".to_vec();
+    let signatures = vec![sign(&key_a, "key-a", &canonical_signed)];
+    let trusted_keys =
+      HashMap::from([("key-a".to_string(), TrustedKeyEntry { kty: "ed25519".to_string(), value: pub_a })]);
+
+    let tampered = b"canonical root bytes!".to_vec();
+    assert!(!verify_threshold(&tampered, &signatures, &trusted_keys, 1).unwrap());
+  }
+
+  #[test]
+  fn verify_threshold_ignores_non_ed25519_entries() {
+    // root.json/keys.json are always self-signed with Ed25519; an entry tagged with
+    // another kty must never be treated as a valid signer even if a signature under
+    // its key id happens to be present.
+    let canonical_signed = b"canonical root bytes".to_vec();
+    let signatures = vec![MetaSignature { keyid: "key-a".to_string(), sig: general_purpose::STANDARD.encode([0u8; 64]) }];
+    let trusted_keys = HashMap::from([(
+      "key-a".to_string(),
+      TrustedKeyEntry { kty: "rsa-spki-der".to_string(), value: general_purpose::STANDARD.encode(b"not an ed25519 key") },
+    )]);
+
+    assert!(!verify_threshold(&canonical_signed, &signatures, &trusted_keys, 1).unwrap());
+  }
+
+  fn temp_cache_dir(label: &str) -> String {
+    let dir = std::env::temp_dir().join(format!(
+      "dps-test-trust-root-{}-{}-{}",
+      label,
+      std::process::id(),
+      chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.to_string_lossy().to_string()
+  }
+
+  fn write_cache(cache_dir: &str, plugin_id: &str, key_id: &str, key_value: &str, expires: &str) {
+    let root = serde_json::json!({
+      "signed": {
+        "type": "root",
+        "version": 1,
+        "expires": expires,
+        "threshold": 0,
+        "keys": { key_id: { "kty": "ed25519", "value": key_value } },
+      },
+      "signatures": [],
+    });
+    let keys = serde_json::json!({
+      "signed": { "type": "keys", "version": 1, "expires": expires, "targets": { plugin_id: [key_id] } },
+      "signatures": [],
+    });
+    std::fs::write(root_path(cache_dir), serde_json::to_string(&root).unwrap()).unwrap();
+    std::fs::write(keys_path(cache_dir), serde_json::to_string(&keys).unwrap()).unwrap();
+  }
+
+  #[test]
+  fn resolve_trusted_key_resolves_registered_key() {
+    let cache_dir = temp_cache_dir("resolve");
+    write_cache(&cache_dir, "plugin-a", "test-key", "pub-key-b64", "2099-01-01T00:00:00Z");
+
+    let resolved = resolve_trusted_key(&cache_dir, "plugin-a", "test-key").unwrap();
+    assert_eq!(resolved, "pub-key-b64");
+  }
+
+  #[test]
+  fn resolve_trusted_key_rejects_expired_root() {
+    let cache_dir = temp_cache_dir("expired");
+    write_cache(&cache_dir, "plugin-a", "test-key", "pub-key-b64", "2000-01-01T00:00:00Z");
+
+    let err = resolve_trusted_key(&cache_dir, "plugin-a", "test-key").unwrap_err();
+    assert!(err.contains("expired"));
+  }
+
+  #[test]
+  fn resolve_trusted_key_rejects_unauthorized_key() {
+    let cache_dir = temp_cache_dir("unauthorized");
+    write_cache(&cache_dir, "plugin-a", "test-key", "pub-key-b64", "2099-01-01T00:00:00Z");
+
+    let err = resolve_trusted_key(&cache_dir, "plugin-a", "other-key").unwrap_err();
+    assert!(err.contains("not authorized"));
+  }
+}